@@ -0,0 +1,81 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A built-in table of WiX culture codes, mapping a `--culture` value to the LCID/codepage pair
+//! needed for the Product `Language`/`Codepage` attributes.
+
+use crate::Error;
+
+/// A resolved culture: the code as spelled in the built-in table, plus its LCID and codepage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Culture {
+    /// The culture code, e.g. `en-US`, exactly as passed to `light -cultures`.
+    pub code: &'static str,
+    /// The Windows LCID used for the Product `Language` attribute.
+    pub lcid: u32,
+    /// The codepage used for the Product `Codepage` attribute.
+    pub codepage: u32,
+}
+
+/// The built-in table of supported culture codes.
+const TABLE: &[Culture] = &[
+    Culture { code: "en-US", lcid: 1033, codepage: 1252 },
+    Culture { code: "en-GB", lcid: 2057, codepage: 1252 },
+    Culture { code: "de-DE", lcid: 1031, codepage: 1252 },
+    Culture { code: "fr-FR", lcid: 1036, codepage: 1252 },
+    Culture { code: "es-ES", lcid: 3082, codepage: 1252 },
+    Culture { code: "it-IT", lcid: 1040, codepage: 1252 },
+    Culture { code: "pt-BR", lcid: 1046, codepage: 1252 },
+    Culture { code: "nl-NL", lcid: 1043, codepage: 1252 },
+    Culture { code: "pl-PL", lcid: 1045, codepage: 1252 },
+    Culture { code: "ru-RU", lcid: 1049, codepage: 1251 },
+    Culture { code: "ja-JP", lcid: 1041, codepage: 932 },
+    Culture { code: "zh-CN", lcid: 2052, codepage: 936 },
+];
+
+/// Looks up `code` in the built-in culture table, case-insensitively.
+///
+/// Returns `Error::Culture` when `code` does not match any entry, so an invalid culture is
+/// rejected before the WiX Toolset is invoked.
+pub fn lookup(code: &str) -> Result<Culture, Error> {
+    TABLE
+        .iter()
+        .find(|culture| culture.code.eq_ignore_ascii_case(code))
+        .cloned()
+        .ok_or_else(|| Error::Culture(code.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_finds_known_cultures() {
+        let culture = lookup("de-DE").unwrap();
+        assert_eq!(culture.code, "de-DE");
+        assert_eq!(culture.lcid, 1031);
+        assert_eq!(culture.codepage, 1252);
+    }
+
+    #[test]
+    fn lookup_is_case_insensitive() {
+        assert_eq!(lookup("EN-us").unwrap().code, "en-US");
+    }
+
+    #[test]
+    fn lookup_rejects_unknown_cultures() {
+        let err = lookup("xx-XX").unwrap_err();
+        assert_eq!(err.code(), Error::Culture("xx-XX".to_string()).code());
+    }
+}
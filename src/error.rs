@@ -0,0 +1,87 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::io;
+
+/// The numeric code returned to the shell on failure, grouped by the stage of the build that
+/// produced the error.
+const GENERIC_ERROR: i32 = 1;
+const IO_ERROR: i32 = 2;
+const MANIFEST_ERROR: i32 = 3;
+const COMMAND_ERROR: i32 = 4;
+const CULTURE_ERROR: i32 = 5;
+
+/// The error type for the `cargo_wix` library.
+#[derive(Debug)]
+pub enum Error {
+    /// A generic, or miscellaneous, error occurred.
+    Generic(String),
+    /// An I/O operation failed.
+    Io(io::Error),
+    /// The package's manifest (Cargo.toml) could not be located or parsed.
+    Manifest(String),
+    /// An external command, e.g. `candle`, `light`, `signtool`, or `makensis`, failed.
+    Command(String, i32),
+    /// A culture code does not match any entry in the built-in culture table.
+    Culture(String),
+}
+
+impl Error {
+    /// Gets the exit code used when this error is printed and the process exits.
+    pub fn code(&self) -> i32 {
+        match *self {
+            Error::Generic(..) => GENERIC_ERROR,
+            Error::Io(..) => IO_ERROR,
+            Error::Manifest(..) => MANIFEST_ERROR,
+            Error::Command(..) => COMMAND_ERROR,
+            Error::Culture(..) => CULTURE_ERROR,
+        }
+    }
+
+    /// Gets a human-readable description of the error.
+    pub fn description(&self) -> String {
+        match *self {
+            Error::Generic(ref msg) => msg.clone(),
+            Error::Io(ref err) => err.to_string(),
+            Error::Manifest(ref msg) => msg.clone(),
+            Error::Command(ref program, code) => {
+                format!("The '{}' application failed with exit code {}", program, code)
+            }
+            Error::Culture(ref culture) => {
+                format!("The '{}' culture code is not recognized", culture)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Error {
+        Error::Manifest(err.to_string())
+    }
+}
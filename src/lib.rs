@@ -0,0 +1,589 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Builds a Windows installer (msi) for a Rust project with the WiX Toolset.
+
+mod culture;
+mod error;
+mod manifest;
+mod nsis;
+mod sign;
+
+pub use crate::error::Error;
+use crate::manifest::Manifest;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The embedded WiX Source (wxs) template used by `init` and `print_template`.
+const TEMPLATE: &str = include_str!("templates/main.wxs");
+
+/// Picks the embedded template and its destination filename for the given `--format`.
+fn template_for_format(format: Option<&str>) -> (&'static str, &'static str) {
+    match format {
+        Some("nsis") => (nsis::TEMPLATE, "main.nsi"),
+        _ => (TEMPLATE, "main.wxs"),
+    }
+}
+
+/// Creates the `wix` subfolder and writes the embedded installer template into it.
+///
+/// The `wix` subfolder is created next to the package's manifest (Cargo.toml), which is located
+/// using `manifest_path` rather than assuming it is in the current working directory.
+pub fn init(force: bool, format: Option<&str>, manifest_path: Option<&str>) -> Result<(), Error> {
+    let manifest_path = Manifest::locate(manifest_path);
+    Manifest::from_path(&manifest_path)?;
+    let project_root = Manifest::project_root(&manifest_path);
+
+    let (template, filename) = template_for_format(format);
+    let wix_dir = project_root.join("wix");
+    fs::create_dir_all(&wix_dir)?;
+    let destination = wix_dir.join(filename);
+    if destination.exists() && !force {
+        return Err(Error::Generic(format!(
+            "'{}' already exists; use '--force' to overwrite it",
+            destination.display()
+        )));
+    }
+    fs::write(&destination, template)?;
+    Ok(())
+}
+
+/// Prints the embedded installer template to stdout.
+///
+/// The package's manifest (Cargo.toml) is located using `manifest_path` rather than assuming it
+/// is in the current working directory, and is read to confirm it exists before printing.
+pub fn print_template(format: Option<&str>, manifest_path: Option<&str>) -> Result<(), Error> {
+    let manifest_path = Manifest::locate(manifest_path);
+    Manifest::from_path(&manifest_path)?;
+
+    let (template, _) = template_for_format(format);
+    print!("{}", template);
+    Ok(())
+}
+
+/// Resolves the `--output` value against the installer that was actually built.
+///
+/// If `output` names an existing directory, or ends with a path separator, the built installer's
+/// filename is kept and it is moved into that directory. Otherwise `output` is treated as the
+/// full destination file path. Either way, any missing parent directories are created.
+fn resolve_output_path(output: &str, built: &Path) -> Result<PathBuf, Error> {
+    let output_path = PathBuf::from(output);
+    let is_directory = output_path.is_dir()
+        || output.ends_with('/')
+        || output.ends_with(std::path::MAIN_SEPARATOR);
+    let destination = if is_directory {
+        let file_name = built
+            .file_name()
+            .ok_or_else(|| Error::Generic("the built installer has no filename".to_string()))?;
+        output_path.join(file_name)
+    } else {
+        output_path
+    };
+    if let Some(parent) = destination.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    Ok(destination)
+}
+
+/// The package fields used to build an installer, resolved from either the `Wix` builder's
+/// overrides or the package's manifest (Cargo.toml), bundled together to keep the build methods'
+/// argument lists manageable.
+struct ResolvedFields {
+    binary_name: String,
+    product_name: String,
+    description: String,
+    manufacturer: String,
+    version: String,
+}
+
+/// A builder for creating a Windows installer for a Rust project.
+#[derive(Debug, Default, Clone)]
+pub struct Wix {
+    banner: Option<String>,
+    binary_name: Option<String>,
+    capture_output: bool,
+    culture: Option<String>,
+    description: Option<String>,
+    dialog: Option<String>,
+    digest_algorithm: Option<String>,
+    format: Option<String>,
+    input: Option<String>,
+    license: Option<String>,
+    locale: Option<String>,
+    manifest_path: Option<String>,
+    manufacturer: Option<String>,
+    output: Option<String>,
+    product_name: Option<String>,
+    sign: bool,
+    sign_command: Option<String>,
+    timestamp: Option<String>,
+}
+
+impl Wix {
+    /// Creates a new builder with all options unset.
+    pub fn new() -> Self {
+        Wix::default()
+    }
+
+    /// A path to a 493x58 pixel, 24-bit BMP image used as the WixUI banner.
+    pub fn banner(mut self, b: Option<&str>) -> Self {
+        self.banner = b.map(String::from);
+        self
+    }
+
+    /// Overrides the package's 'name' field of the manifest as the installed binary's name.
+    pub fn binary_name(mut self, b: Option<&str>) -> Self {
+        self.binary_name = b.map(String::from);
+        self
+    }
+
+    /// Whether to capture, or hide, the output of the build, compiler, linker, and signer.
+    pub fn capture_output(mut self, c: bool) -> Self {
+        self.capture_output = c;
+        self
+    }
+
+    /// The culture code (e.g. `en-US`) used to build a localized installer.
+    pub fn culture(mut self, c: Option<&str>) -> Self {
+        self.culture = c.map(String::from);
+        self
+    }
+
+    /// Overrides the 'description' field of the manifest as the installer's description.
+    pub fn description(mut self, d: Option<&str>) -> Self {
+        self.description = d.map(String::from);
+        self
+    }
+
+    /// A path to a 493x312 pixel, 24-bit BMP image used as the WixUI dialog background.
+    pub fn dialog(mut self, d: Option<&str>) -> Self {
+        self.dialog = d.map(String::from);
+        self
+    }
+
+    /// The file digest algorithm passed to `signtool` when signing the installer.
+    pub fn digest_algorithm(mut self, d: Option<&str>) -> Self {
+        self.digest_algorithm = d.map(String::from);
+        self
+    }
+
+    /// The installer backend to build, either `msi` or `nsis`.
+    pub fn format(mut self, f: Option<&str>) -> Self {
+        self.format = f.map(String::from);
+        self
+    }
+
+    /// A WiX Source (wxs) file to use instead of the default `wix/main.wxs`.
+    pub fn input(mut self, i: Option<&str>) -> Self {
+        self.input = i.map(String::from);
+        self
+    }
+
+    /// A path to an RTF file used as the WixUI license page.
+    pub fn license(mut self, l: Option<&str>) -> Self {
+        self.license = l.map(String::from);
+        self
+    }
+
+    /// A path to a WiX localization (wxl) file.
+    pub fn locale(mut self, l: Option<&str>) -> Self {
+        self.locale = l.map(String::from);
+        self
+    }
+
+    /// A path to the package's manifest (Cargo.toml) to use instead of the one in the current
+    /// working directory.
+    pub fn manifest_path(mut self, m: Option<&str>) -> Self {
+        self.manifest_path = m.map(String::from);
+        self
+    }
+
+    /// Overrides the first author in the manifest's 'authors' field as the installer's manufacturer.
+    pub fn manufacturer(mut self, m: Option<&str>) -> Self {
+        self.manufacturer = m.map(String::from);
+        self
+    }
+
+    /// Overrides the destination, directory or full file path, of the built installer.
+    pub fn output(mut self, o: Option<&str>) -> Self {
+        self.output = o.map(String::from);
+        self
+    }
+
+    /// Overrides the 'name' field of the manifest as the installer's product name.
+    pub fn product_name(mut self, p: Option<&str>) -> Self {
+        self.product_name = p.map(String::from);
+        self
+    }
+
+    /// Whether the built installer should be signed.
+    pub fn sign(mut self, s: bool) -> Self {
+        self.sign = s;
+        self
+    }
+
+    /// A command template used to sign the installer instead of the built-in `signtool` invocation.
+    pub fn sign_command(mut self, s: Option<&str>) -> Self {
+        self.sign_command = s.map(String::from);
+        self
+    }
+
+    /// The URL for the timestamp server used when signing the installer.
+    pub fn timestamp(mut self, t: Option<&str>) -> Self {
+        self.timestamp = t.map(String::from);
+        self
+    }
+
+    /// Builds the release binary and packages it into an installer.
+    pub fn run(&self) -> Result<(), Error> {
+        let manifest_path = Manifest::locate(self.manifest_path.as_deref());
+        let manifest = Manifest::from_path(&manifest_path)?;
+        // Made absolute so that the child processes, which run with the project root as their
+        // working directory, can be given paths under it without resolving them twice.
+        let project_root = std::env::current_dir()?.join(Manifest::project_root(&manifest_path));
+
+        let fields = ResolvedFields {
+            binary_name: self.binary_name.clone().unwrap_or_else(|| manifest.name.clone()),
+            product_name: self.product_name.clone().unwrap_or_else(|| manifest.name.clone()),
+            description: self
+                .description
+                .clone()
+                .unwrap_or_else(|| manifest.description.clone().unwrap_or_default()),
+            manufacturer: self
+                .manufacturer
+                .clone()
+                .or_else(|| manifest.manufacturer().map(String::from))
+                .unwrap_or_default(),
+            version: manifest.version.clone(),
+        };
+
+        let format = self.format.as_deref().unwrap_or("msi");
+        // Rejecting an unknown culture code happens before any external tool is invoked.
+        let culture = self.culture.as_deref().map(culture::lookup).transpose()?;
+
+        self.build_binary(&project_root)?;
+        let mut installer_path = match format {
+            "nsis" => self.build_nsis(&project_root, &fields)?,
+            "msi" => self.build_msi(&project_root, &fields, culture.as_ref())?,
+            other => {
+                return Err(Error::Generic(format!(
+                    "'{}' is not a recognized installer format; expected 'msi' or 'nsis'",
+                    other
+                )))
+            }
+        };
+
+        if let Some(output) = &self.output {
+            let output_path = resolve_output_path(output, &installer_path)?;
+            fs::rename(&installer_path, &output_path)?;
+            installer_path = output_path;
+        }
+
+        if self.sign {
+            self.sign_installer(&installer_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn build_nsis(&self, project_root: &Path, fields: &ResolvedFields) -> Result<PathBuf, Error> {
+        let nsis_target_dir = project_root.join("target").join("nsis");
+        fs::create_dir_all(&nsis_target_dir)?;
+
+        let script = nsis::render(&fields.product_name, &fields.version, &fields.manufacturer, &fields.binary_name);
+        let script_path = nsis_target_dir.join("main.nsi");
+        fs::write(&script_path, script)?;
+
+        let release_binary = project_root
+            .join("target")
+            .join("release")
+            .join(format!("{}.exe", fields.binary_name));
+        fs::copy(&release_binary, nsis_target_dir.join(format!("{}.exe", fields.binary_name)))?;
+
+        let mut makensis = Command::new("makensis");
+        makensis.current_dir(&nsis_target_dir).arg(&script_path);
+        if self.capture_output {
+            makensis.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        }
+        let status = makensis.status()?;
+        if !status.success() {
+            return Err(Error::Command("makensis".to_string(), status.code().unwrap_or(1)));
+        }
+
+        Ok(nsis_target_dir.join(nsis::installer_name(&fields.product_name, &fields.version)))
+    }
+
+    fn build_binary(&self, project_root: &Path) -> Result<(), Error> {
+        let mut command = Command::new("cargo");
+        command.current_dir(project_root).arg("build").arg("--release");
+        if self.capture_output {
+            command.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        }
+        let status = command.status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::Command("cargo build".to_string(), status.code().unwrap_or(1)))
+        }
+    }
+
+    fn build_msi(
+        &self,
+        project_root: &Path,
+        fields: &ResolvedFields,
+        culture: Option<&culture::Culture>,
+    ) -> Result<PathBuf, Error> {
+        let wxs_source = self.input.clone().unwrap_or_else(|| "wix/main.wxs".to_string());
+        let wix_target_dir = project_root.join("target").join("wix");
+        fs::create_dir_all(&wix_target_dir)?;
+        let wixobj_path = wix_target_dir.join("main.wixobj");
+
+        let (language, codepage) = culture.map_or((1033, 1252), |c| (c.lcid, c.codepage));
+
+        let mut candle = Command::new("candle");
+        candle
+            .current_dir(project_root)
+            .arg("-nologo")
+            .arg(format!("-dProductName={}", fields.product_name))
+            .arg(format!("-dManufacturer={}", fields.manufacturer))
+            .arg(format!("-dVersion={}", fields.version))
+            .arg(format!("-dDescription={}", fields.description))
+            .arg(format!("-dBinary={}.exe", fields.binary_name))
+            .arg(format!("-dLanguage={}", language))
+            .arg(format!("-dCodepage={}", codepage))
+            .arg(format!(
+                "-dCargoTargetBinDir={}",
+                project_root.join("target").join("release").display()
+            ));
+        if let Some(banner) = &self.banner {
+            candle.arg(format!("-dWixUIBannerBmp={}", banner));
+        }
+        if let Some(dialog) = &self.dialog {
+            candle.arg(format!("-dWixUIDialogBmp={}", dialog));
+        }
+        if let Some(license) = &self.license {
+            candle.arg(format!("-dWixUILicenseRtf={}", license));
+        }
+        candle.arg("-out").arg(&wixobj_path).arg(&wxs_source);
+        if self.capture_output {
+            candle.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        }
+        let status = candle.status()?;
+        if !status.success() {
+            return Err(Error::Command("candle".to_string(), status.code().unwrap_or(1)));
+        }
+
+        let msi_name = format!("{}-{}-x86_64.msi", fields.binary_name, fields.version);
+        let msi_path = wix_target_dir.join(msi_name);
+        let mut light = Command::new("light");
+        light
+            .current_dir(project_root)
+            .arg("-nologo")
+            .arg("-ext")
+            .arg("WixUIExtension")
+            .arg("-out")
+            .arg(&msi_path)
+            .arg(&wixobj_path);
+        if let Some(culture) = culture {
+            light.arg(format!("-cultures:{}", culture.code));
+        }
+        if let Some(locale) = &self.locale {
+            light.arg("-loc").arg(locale);
+        }
+        if self.capture_output {
+            light.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        }
+        let status = light.status()?;
+        if !status.success() {
+            return Err(Error::Command("light".to_string(), status.code().unwrap_or(1)));
+        }
+
+        Ok(msi_path)
+    }
+
+    fn sign_installer(&self, installer_path: &Path) -> Result<(), Error> {
+        let digest_algorithm = self.digest_algorithm.as_deref().unwrap_or("sha256");
+        let (program, args) = if let Some(template) = &self.sign_command {
+            let mut tokens =
+                sign::render_sign_command(template, installer_path, digest_algorithm, self.timestamp.as_deref());
+            if tokens.is_empty() {
+                return Err(Error::Generic("'--sign-command' produced an empty command".to_string()));
+            }
+            let program = tokens.remove(0);
+            (program, tokens)
+        } else {
+            (
+                "signtool".to_string(),
+                sign::default_signtool_command(installer_path, digest_algorithm, self.timestamp.as_deref()),
+            )
+        };
+        let mut command = Command::new(&program);
+        command.args(&args);
+        if self.capture_output {
+            command.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        }
+        let status = command.status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::Command(program, status.code().unwrap_or(1)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn init_creates_the_wix_folder_next_to_a_custom_manifest_path() {
+        let dir = std::env::temp_dir().join("cargo-wix-init-test-1");
+        fs::create_dir_all(&dir).unwrap();
+        let manifest_path = dir.join("Cargo.toml");
+        fs::write(&manifest_path, "[package]\nname = \"example\"\nversion = \"1.0.0\"\n").unwrap();
+
+        init(false, None, Some(manifest_path.to_str().unwrap())).unwrap();
+
+        assert!(dir.join("wix").join("main.wxs").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn init_fails_when_the_manifest_path_does_not_exist() {
+        let result = init(false, None, Some("does/not/exist/Cargo.toml"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn print_template_fails_when_the_manifest_path_does_not_exist() {
+        let result = print_template(None, Some("does/not/exist/Cargo.toml"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn print_template_succeeds_with_a_valid_manifest_path() {
+        let dir = std::env::temp_dir().join("cargo-wix-print-template-test-1");
+        fs::create_dir_all(&dir).unwrap();
+        let manifest_path = dir.join("Cargo.toml");
+        fs::write(&manifest_path, "[package]\nname = \"example\"\nversion = \"1.0.0\"\n").unwrap();
+
+        assert!(print_template(None, Some(manifest_path.to_str().unwrap())).is_ok());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn wxs_template_binds_the_ui_asset_variables() {
+        assert!(TEMPLATE.contains("Id='WixUIBannerBmp' Value='$(var.WixUIBannerBmp)'"));
+        assert!(TEMPLATE.contains("Id='WixUIDialogBmp' Value='$(var.WixUIDialogBmp)'"));
+        assert!(TEMPLATE.contains("Id='WixUILicenseRtf' Value='$(var.WixUILicenseRtf)'"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn build_msi_defines_the_ui_asset_variables_on_candle_not_light() {
+        // The '<?ifdef?>' blocks that bind the UI asset variables are evaluated by 'candle'
+        // while compiling the wxs source, not by 'light', which only consumes the already
+        // compiled wixobj. Fake 'candle'/'light' scripts record their argv so this can be
+        // checked without the real WiX Toolset installed.
+        let dir = std::env::temp_dir().join("cargo-wix-build-msi-ui-asset-test-1");
+        let bin_dir = dir.join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        fs::create_dir_all(dir.join("wix")).unwrap();
+        fs::write(dir.join("wix").join("main.wxs"), TEMPLATE).unwrap();
+
+        for (name, argv_file) in &[("candle", "candle.argv"), ("light", "light.argv")] {
+            let script = bin_dir.join(name);
+            fs::write(&script, format!("#!/bin/sh\necho \"$@\" > \"{}\"\n", dir.join(argv_file).display())).unwrap();
+            let mut perms = fs::metadata(&script).unwrap().permissions();
+            perms.set_mode(0o755);
+            fs::set_permissions(&script, perms).unwrap();
+        }
+
+        let original_path = std::env::var("PATH").unwrap_or_default();
+        std::env::set_var("PATH", format!("{}:{}", bin_dir.display(), original_path));
+
+        let fields = ResolvedFields {
+            binary_name: "example".to_string(),
+            product_name: "Example".to_string(),
+            description: "An example".to_string(),
+            manufacturer: "Jane Doe".to_string(),
+            version: "1.0.0".to_string(),
+        };
+        let wix = Wix::new().banner(Some("banner.bmp")).dialog(Some("dialog.bmp")).license(Some("license.rtf"));
+        let result = wix.build_msi(&dir, &fields, None);
+
+        std::env::set_var("PATH", original_path);
+
+        assert!(result.is_ok());
+        let candle_argv = fs::read_to_string(dir.join("candle.argv")).unwrap();
+        let light_argv = fs::read_to_string(dir.join("light.argv")).unwrap();
+        assert!(candle_argv.contains("-dWixUIBannerBmp=banner.bmp"));
+        assert!(candle_argv.contains("-dWixUIDialogBmp=dialog.bmp"));
+        assert!(candle_argv.contains("-dWixUILicenseRtf=license.rtf"));
+        assert!(!light_argv.contains("-dWixUIBannerBmp"));
+        assert!(!light_argv.contains("-dWixUIDialogBmp"));
+        assert!(!light_argv.contains("-dWixUILicenseRtf"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn run_rejects_an_unknown_culture_before_building_anything() {
+        let dir = std::env::temp_dir().join("cargo-wix-run-culture-test-1");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"example\"\nversion = \"1.0.0\"\n").unwrap();
+
+        let result = Wix::new()
+            .manifest_path(Some(dir.join("Cargo.toml").to_str().unwrap()))
+            .culture(Some("xx-XX"))
+            .run();
+
+        assert!(matches!(result, Err(Error::Culture(ref code)) if code.as_str() == "xx-XX"));
+        // No 'target' directory should have been created, since run() fails before cargo build.
+        assert!(!dir.join("target").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn resolve_output_path_keeps_filename_for_an_existing_directory() {
+        let dir = std::env::temp_dir().join("cargo-wix-output-test-1");
+        fs::create_dir_all(&dir).unwrap();
+        let built = Path::new("target/wix/example-1.0.0-x86_64.msi");
+        let resolved = resolve_output_path(dir.to_str().unwrap(), built).unwrap();
+        assert_eq!(resolved, dir.join("example-1.0.0-x86_64.msi"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn resolve_output_path_treats_trailing_separator_as_a_directory() {
+        let built = Path::new("target/wix/example-1.0.0-x86_64.msi");
+        let resolved = resolve_output_path("dist/", built).unwrap();
+        assert_eq!(resolved, PathBuf::from("dist/example-1.0.0-x86_64.msi"));
+        fs::remove_dir_all("dist").ok();
+    }
+
+    #[test]
+    fn resolve_output_path_treats_a_non_directory_as_the_full_file_path() {
+        let built = Path::new("target/wix/example-1.0.0-x86_64.msi");
+        let resolved = resolve_output_path("dist/custom-name.msi", built).unwrap();
+        assert_eq!(resolved, PathBuf::from("dist/custom-name.msi"));
+        fs::remove_dir_all("dist").ok();
+    }
+}
@@ -22,7 +22,6 @@ extern crate loggerv;
 
 use ansi_term::Colour;
 use clap::{App, Arg, SubCommand};
-use std::error::Error;
 use std::io::Write;
 
 const SUBCOMMAND_NAME: &str = "wix";
@@ -43,23 +42,58 @@ fn main() {
                 .version(crate_version!())
                 .about(crate_description!())
                 .author(crate_authors!())
+                .arg(Arg::with_name("banner")
+                     .help("A path to a 493x58 pixel, 24-bit BMP image that is used as the top banner in the installer's WixUI dialogs. Bound to the 'WixUIBannerBmp' variable passed to 'candle'.")
+                     .long("banner")
+                     .takes_value(true))
                 .arg(Arg::with_name("binary-name")
                      .help("Overrides the 'name' field of the bin section of the package's manifest (Cargo.toml) as the name of the executable within the installer.")
                      .long("binary-name")
                      .short("b")
                      .takes_value(true))
+                .arg(Arg::with_name("culture")
+                     .help("Builds a localized installer using the given culture code (e.g. 'en-US', 'de-DE', 'fr-FR'). The culture is resolved against a built-in table of culture codes to LCID/language-id values, which is used to set both the '-cultures' flag passed to 'light' and the Product 'Language' attribute. An unknown culture code is rejected before the toolset is invoked.")
+                     .long("culture")
+                     .takes_value(true))
                 .arg(Arg::with_name("description")
                      .help("Overrides the 'description' field of the package's manifest (Cargo.toml) as the description within the installer.")
                      .long("description")
                      .short("d")
                      .takes_value(true))
+                .arg(Arg::with_name("dialog")
+                     .help("A path to a 493x312 pixel, 24-bit BMP image that is used as the background for the welcome and completion WixUI dialogs. Bound to the 'WixUIDialogBmp' variable passed to 'candle'.")
+                     .long("dialog")
+                     .takes_value(true))
+                .arg(Arg::with_name("digest-algorithm")
+                     .help("The file digest algorithm passed to 'signtool' with the '/fd' flag and, when timestamping, the '/td' flag. Modern versions of signtool require this to be explicitly specified. Defaults to 'sha256' when signing without this option. This can only be used with the '-s,--sign' flag.")
+                     .long("digest-algorithm")
+                     .takes_value(true)
+                     .requires("sign"))
                 .arg(Arg::with_name("force")
                      .help("Overwrites any existing WiX Source files when using the '--init' flag. Use with caution.")
                      .long("force")
                      .requires("init"))
+                .arg(Arg::with_name("format")
+                     .help("The installer backend to build. 'msi' builds a Windows installer (msi) with the WiX Toolset, which is the default. 'nsis' builds a self-contained executable installer with NSIS, invoking 'makensis' against an embedded '.nsi' template analogous to the embedded WiX template.")
+                     .long("format")
+                     .takes_value(true)
+                     .possible_values(&["msi", "nsis"])
+                     .default_value("msi"))
                 .arg(Arg::with_name("init")
                      .help("Initializes the package to be used with this subcommand. This creates a 'wix` sub-folder within the root folder of the package and creates a 'main.wxs' WiX Source (wxs) file within the 'wix' sub-folder from the embedded template. The 'wix\\main.wxs' file that is created can immediately be used with this subcommand without modification to create an installer for the project.")
                      .long("init"))
+                .arg(Arg::with_name("license")
+                     .help("A path to an RTF file that is used as the license page in the WixUI_Minimal/InstallDir installer UI. Bound to the 'WixUILicenseRtf' variable passed to 'candle'.")
+                     .long("license")
+                     .takes_value(true))
+                .arg(Arg::with_name("locale")
+                     .help("A path to a WiX localization (wxl) file to use with the '-loc' flag when running 'light'. This can be combined with '--culture' to produce a fully localized installer.")
+                     .long("locale")
+                     .takes_value(true))
+                .arg(Arg::with_name("manifest-path")
+                     .help("Sets the path to the package's manifest (Cargo.toml) to use when locating the 'Cargo.toml' file and resolving the package's 'name', 'description', 'authors', and 'version' fields. The default is to use the 'Cargo.toml' file in the current working directory.")
+                     .long("manifest-path")
+                     .takes_value(true))
                 .arg(Arg::with_name("manufacturer")
                      .help("Overrides the first author in the 'authors' field of the package's manifest (Cargo.toml) as the manufacturer within the installer.")
                      .long("manufacturer")
@@ -68,6 +102,11 @@ fn main() {
                 .arg(Arg::with_name("no-capture")
                      .help("By default, this subcommand captures, or hides, all output from the builder, compiler, linker, and signer for the binary and Windows installer, respectively. Use this flag to show the output.")
                      .long("nocapture"))
+                .arg(Arg::with_name("output")
+                     .help("Sets the destination for the built installer, completely overriding the default filename and location. Accepts either a directory, in which case the default filename is kept, or a full file path.")
+                     .long("output")
+                     .short("o")
+                     .takes_value(true))
                 .arg(Arg::with_name("print-template")
                      .help("Prints a template WiX Source (wxs) file to use with this subcommand to stdout. The template provided with this subcommand uses xml preprocessor varaibles to set values based on fields in the rust project's manifest file (Cargo.toml). Only the '{{replace-with-a-guid}}' placeholders within the template need to be modified with unique GUIDs by hand. Redirection can be used to save the contents to 'main.wxs' and then placed in the 'wix' subfolder.")
                      .long("print-template"))
@@ -80,6 +119,12 @@ fn main() {
                      .help("The Windows installer (msi) will be signed using the SignTool application available in the Windows 10 SDK. The signtool is invoked with the '/a' flag to automatically obtain an appropriate certificate from the Windows certificate manager. The default is to also use the Comodo timestamp server with the '/t' flag.")
                      .short("s")
                      .long("sign"))
+                .arg(Arg::with_name("sign-command")
+                     .help("Signs the Windows installer (msi) by running the given command template instead of the built-in 'signtool' invocation. The template is tokenized into a program and its arguments and run with the system shell disabled. The '{{path}}' placeholder is replaced with the path to the msi, quoted if necessary, and the optional '{{digest_algorithm}}' placeholder is replaced with the value of the '--digest-algorithm' option. The '{{timestamp_url}}' placeholder is replaced with an empty string, since the '--timestamp' option cannot be combined with this one. This is useful for signing with tools other than 'signtool', such as 'osslsigncode', a hardware token, an HSM, or a cloud signing service. This cannot be used with the '-t,--timestamp' flag since the template is expected to fully own the signing invocation.")
+                     .long("sign-command")
+                     .takes_value(true)
+                     .requires("sign")
+                     .conflicts_with("timestamp"))
                 .arg(Arg::with_name("timestamp")
                      .help("The URL for the timestamp server used with the 'signtool' to sign the installer. This can only be used with the '-s,--sign' flag.")
                      .short("t")
@@ -109,18 +154,28 @@ fn main() {
     .init()
     .expect("logger to initiate");
     let result = if matches.is_present("init") {
-        cargo_wix::init(matches.is_present("force"))
+        cargo_wix::init(matches.is_present("force"), matches.value_of("format"), matches.value_of("manifest-path"))
     } else if matches.is_present("print-template") {
-        cargo_wix::print_template()
+        cargo_wix::print_template(matches.value_of("format"), matches.value_of("manifest-path"))
     } else {
         cargo_wix::Wix::new()
+            .banner(matches.value_of("banner"))
             .binary_name(matches.value_of("binary-name"))
             .capture_output(!matches.is_present("no-capture"))
+            .culture(matches.value_of("culture"))
             .description(matches.value_of("description"))
+            .dialog(matches.value_of("dialog"))
+            .digest_algorithm(matches.value_of("digest-algorithm"))
+            .format(matches.value_of("format"))
             .input(matches.value_of("INPUT"))
+            .license(matches.value_of("license"))
+            .locale(matches.value_of("locale"))
+            .manifest_path(matches.value_of("manifest-path"))
             .manufacturer(matches.value_of("manufacturer"))
+            .output(matches.value_of("output"))
             .product_name(matches.value_of("product-name"))
             .sign(matches.is_present("sign"))
+            .sign_command(matches.value_of("sign-command"))
             .timestamp(matches.value_of("timestamp"))
             .run()
     };
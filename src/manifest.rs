@@ -0,0 +1,141 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Locating and parsing the package's manifest (Cargo.toml).
+
+use crate::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The subset of a package's manifest (Cargo.toml) that drives the installer template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// The `package.name` field.
+    pub name: String,
+    /// The `package.version` field.
+    pub version: String,
+    /// The `package.description` field, if present.
+    pub description: Option<String>,
+    /// The `package.authors` field.
+    pub authors: Vec<String>,
+}
+
+impl Manifest {
+    /// Resolves the path to the package's manifest (Cargo.toml).
+    ///
+    /// If `manifest_path` is `None`, the `Cargo.toml` file in the current working directory is
+    /// used, matching `cargo`'s own default.
+    pub fn locate(manifest_path: Option<&str>) -> PathBuf {
+        manifest_path.map(PathBuf::from).unwrap_or_else(|| PathBuf::from("Cargo.toml"))
+    }
+
+    /// Reads and parses the manifest at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| {
+            Error::Manifest(format!("could not read '{}': {}", path.display(), e))
+        })?;
+        let value = content.parse::<toml::Value>()?;
+        let package = value
+            .get("package")
+            .ok_or_else(|| Error::Manifest(format!("no '[package]' table in '{}'", path.display())))?;
+        let name = package
+            .get("name")
+            .and_then(toml::Value::as_str)
+            .ok_or_else(|| Error::Manifest("missing 'package.name' field".to_string()))?
+            .to_string();
+        let version = package
+            .get("version")
+            .and_then(toml::Value::as_str)
+            .ok_or_else(|| Error::Manifest("missing 'package.version' field".to_string()))?
+            .to_string();
+        let description = package
+            .get("description")
+            .and_then(toml::Value::as_str)
+            .map(String::from);
+        let authors = package
+            .get("authors")
+            .and_then(toml::Value::as_array)
+            .map(|a| a.iter().filter_map(toml::Value::as_str).map(String::from).collect())
+            .unwrap_or_default();
+        Ok(Manifest { name, version, description, authors })
+    }
+
+    /// The directory containing the manifest, used as the project root when locating the `wix`
+    /// subfolder and the release binary.
+    pub fn project_root<P: AsRef<Path>>(path: P) -> PathBuf {
+        path.as_ref().parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// The first author in the `authors` field, used as the default manufacturer.
+    pub fn manufacturer(&self) -> Option<&str> {
+        self.authors.first().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_manifest(dir: &Path, content: &str) -> PathBuf {
+        let path = dir.join("Cargo.toml");
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn from_path_parses_package_fields() {
+        let dir = std::env::temp_dir().join("cargo-wix-manifest-test-1");
+        fs::create_dir_all(&dir).unwrap();
+        let path = write_manifest(
+            &dir,
+            r#"
+            [package]
+            name = "example"
+            version = "1.2.3"
+            description = "An example"
+            authors = ["Jane Doe <jane@example.com>"]
+            "#,
+        );
+        let manifest = Manifest::from_path(&path).unwrap();
+        assert_eq!(manifest.name, "example");
+        assert_eq!(manifest.version, "1.2.3");
+        assert_eq!(manifest.description.as_deref(), Some("An example"));
+        assert_eq!(manifest.manufacturer(), Some("Jane Doe <jane@example.com>"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn from_path_missing_name_is_an_error() {
+        let dir = std::env::temp_dir().join("cargo-wix-manifest-test-2");
+        fs::create_dir_all(&dir).unwrap();
+        let path = write_manifest(&dir, "[package]\nversion = \"1.0.0\"\n");
+        assert!(Manifest::from_path(&path).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn locate_defaults_to_cargo_toml() {
+        assert_eq!(Manifest::locate(None), PathBuf::from("Cargo.toml"));
+        assert_eq!(Manifest::locate(Some("nested/Cargo.toml")), PathBuf::from("nested/Cargo.toml"));
+    }
+
+    #[test]
+    fn project_root_is_the_manifest_parent() {
+        assert_eq!(Manifest::project_root(Path::new("nested/Cargo.toml")), PathBuf::from("nested"));
+        assert_eq!(Manifest::project_root(Path::new("Cargo.toml")), PathBuf::from(""));
+    }
+}
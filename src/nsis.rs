@@ -0,0 +1,54 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The NSIS installer backend, an alternative to the WiX/MSI backend for producing a lightweight,
+//! self-contained `.exe` installer.
+
+/// The embedded NSIS script (`.nsi`) template used by the NSIS backend.
+pub const TEMPLATE: &str = include_str!("templates/main.nsi");
+
+/// Substitutes the `{{product_name}}`, `{{product_version}}`, `{{manufacturer}}`, and
+/// `{{binary_name}}` placeholders in the embedded NSIS template with values resolved from the
+/// package's manifest (Cargo.toml).
+pub fn render(product_name: &str, product_version: &str, manufacturer: &str, binary_name: &str) -> String {
+    TEMPLATE
+        .replace("{{product_name}}", product_name)
+        .replace("{{product_version}}", product_version)
+        .replace("{{manufacturer}}", manufacturer)
+        .replace("{{binary_name}}", binary_name)
+}
+
+/// The filename of the installer produced by the NSIS backend for a given product and version.
+pub fn installer_name(product_name: &str, product_version: &str) -> String {
+    format!("{}-{}-x86_64.exe", product_name, product_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_substitutes_all_placeholders() {
+        let rendered = render("Example", "1.2.3", "Jane Doe", "example");
+        assert!(rendered.contains("Name \"Example\""));
+        assert!(rendered.contains("VIAddVersionKey \"CompanyName\" \"Jane Doe\""));
+        assert!(rendered.contains("File \"example.exe\""));
+        assert!(!rendered.contains("{{"));
+    }
+
+    #[test]
+    fn installer_name_matches_msi_naming_convention() {
+        assert_eq!(installer_name("example", "1.2.3"), "example-1.2.3-x86_64.exe");
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (C) 2017 Christopher R. Field.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing the built installer, either with the built-in `signtool` invocation or with a
+//! user-supplied `--sign-command` template.
+
+use std::path::Path;
+
+/// Quotes `path` with double quotes if it contains a space, otherwise returns it unchanged.
+pub fn quote_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if path.contains(' ') {
+        format!("\"{}\"", path)
+    } else {
+        path.into_owned()
+    }
+}
+
+/// Splits a command line template into a program and its arguments.
+///
+/// This is a minimal shell-like tokenizer: it splits on whitespace but treats a
+/// double-quoted span as a single token, which is enough to keep a quoted `{{path}}`
+/// substitution together as one argument.
+pub fn tokenize(template: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_token = false;
+    for c in template.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_token = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_token {
+                    tokens.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+    if has_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Replaces the `{{path}}`, `{{digest_algorithm}}`, and `{{timestamp_url}}` placeholders in a
+/// `--sign-command` template and tokenizes the result into a program and its arguments.
+pub fn render_sign_command(
+    template: &str,
+    path: &Path,
+    digest_algorithm: &str,
+    timestamp: Option<&str>,
+) -> Vec<String> {
+    let rendered = template
+        .replace("{{path}}", &quote_path(path))
+        .replace("{{digest_algorithm}}", digest_algorithm)
+        .replace("{{timestamp_url}}", timestamp.unwrap_or(""));
+    tokenize(&rendered)
+}
+
+/// Builds the default `signtool sign ...` invocation used when `--sign-command` is not given.
+///
+/// Modern versions of `signtool` require the file digest algorithm to be passed explicitly with
+/// `/fd`; when timestamping, the same algorithm is passed to the timestamp server with `/td`.
+pub fn default_signtool_command(path: &Path, digest_algorithm: &str, timestamp: Option<&str>) -> Vec<String> {
+    let mut args = vec![
+        "sign".to_string(),
+        "/a".to_string(),
+        "/fd".to_string(),
+        digest_algorithm.to_string(),
+    ];
+    if let Some(url) = timestamp {
+        args.push("/td".to_string());
+        args.push(digest_algorithm.to_string());
+        args.push("/t".to_string());
+        args.push(url.to_string());
+    }
+    args.push(path.to_string_lossy().into_owned());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_path_wraps_paths_with_spaces() {
+        assert_eq!(quote_path(Path::new("C:/Program Files/app.msi")), "\"C:/Program Files/app.msi\"");
+        assert_eq!(quote_path(Path::new("C:/app.msi")), "C:/app.msi");
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace_and_respects_quotes() {
+        assert_eq!(
+            tokenize("osslsigncode sign -in \"C:/Program Files/app.msi\" -out app.msi"),
+            vec!["osslsigncode", "sign", "-in", "C:/Program Files/app.msi", "-out", "app.msi"]
+        );
+    }
+
+    #[test]
+    fn render_sign_command_substitutes_placeholders() {
+        let rendered = render_sign_command(
+            "osslsigncode sign -h {{digest_algorithm}} -t {{timestamp_url}} -in {{path}}",
+            Path::new("C:/Program Files/app.msi"),
+            "sha256",
+            Some("http://timestamp.example.com"),
+        );
+        assert_eq!(
+            rendered,
+            vec![
+                "osslsigncode",
+                "sign",
+                "-h",
+                "sha256",
+                "-t",
+                "http://timestamp.example.com",
+                "-in",
+                "C:/Program Files/app.msi",
+            ]
+        );
+    }
+
+    #[test]
+    fn default_signtool_command_includes_digest_algorithm() {
+        let args = default_signtool_command(Path::new("app.msi"), "sha256", None);
+        assert_eq!(args, vec!["sign", "/a", "/fd", "sha256", "app.msi"]);
+    }
+
+    #[test]
+    fn default_signtool_command_adds_td_and_timestamp_when_present() {
+        let args = default_signtool_command(Path::new("app.msi"), "sha256", Some("http://timestamp.example.com"));
+        assert_eq!(
+            args,
+            vec![
+                "sign",
+                "/a",
+                "/fd",
+                "sha256",
+                "/td",
+                "sha256",
+                "/t",
+                "http://timestamp.example.com",
+                "app.msi",
+            ]
+        );
+    }
+}